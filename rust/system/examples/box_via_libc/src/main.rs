@@ -0,0 +1,68 @@
+//! Runnable counterpart to the "§4 Standard Library Usage" notes' claim
+//! that `Box`/`Vec` route through the allocator down to libc `malloc`.
+//!
+//! This is `#![no_std]` with its own `_start`, but unlike `no_std_hello`
+//! it wires `#[global_allocator]` straight to `libc::malloc`/`libc::free`,
+//! so `Box::new` really does walk allocator -> libc -> syscall end to end.
+//! Needs the nightly `lang_items` feature for `eh_personality`. Build with
+//! `cargo +nightly run` from this directory.
+
+#![no_std]
+#![no_main]
+#![feature(lang_items)]
+#![feature(core_intrinsics)]
+#![allow(internal_features)]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use core::alloc::{GlobalAlloc, Layout};
+use core::intrinsics::abort;
+use core::panic::PanicInfo;
+
+/// glibc's `malloc` hands out memory aligned to at least this many bytes
+/// on x86_64; anything stricter needs `aligned_alloc`.
+const MALLOC_MIN_ALIGN: usize = 16;
+
+struct LibcAllocator;
+
+unsafe impl GlobalAlloc for LibcAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = if layout.align() <= MALLOC_MIN_ALIGN {
+            libc::malloc(layout.size())
+        } else {
+            libc::aligned_alloc(layout.align(), layout.size().next_multiple_of(layout.align()))
+        };
+        if ptr.is_null() {
+            abort();
+        }
+        ptr as *mut u8
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        libc::free(ptr as *mut core::ffi::c_void);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: LibcAllocator = LibcAllocator;
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    abort()
+}
+
+// `panic = "abort"` (set in `.cargo/config.toml`) means our own code never
+// unwinds, but the precompiled `alloc`/`core` sysroot crates still
+// reference this lang item, so it has to resolve even though it's never
+// actually called.
+#[lang = "eh_personality"]
+extern "C" fn eh_personality() {}
+
+#[no_mangle]
+pub extern "C" fn _start() -> ! {
+    let boxed = Box::new(41u32 + 1);
+    let ok = *boxed == 42;
+    drop(boxed);
+    unsafe { libc::exit(if ok { 0 } else { 1 }) }
+}
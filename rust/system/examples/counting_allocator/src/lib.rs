@@ -0,0 +1,290 @@
+//! Runnable counterpart to the "§8 Memory & Allocator" notes.
+//!
+//! [`CountingAllocator`] wraps any [`GlobalAlloc`] (defaulting to [`System`])
+//! and records bytes allocated/freed, peak resident bytes, and allocation
+//! count via atomics, so the notes' "can swap with jemalloc, mimalloc,
+//! custom arenas" claim has something concrete to measure against. Wrap a
+//! workload in [`Region::measure`] to see the counters for just that
+//! closure, e.g. a `Vec`/`String`/`Box` allocation pattern.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Max nesting depth for [`Region`]s on a single thread. A fixed-size,
+/// `Drop`-free array is used (rather than a `Vec` behind a `RefCell`) so
+/// that touching this thread-local from inside `alloc`/`dealloc` can never
+/// itself trigger an allocation and recurse back into this allocator.
+const MAX_REGION_DEPTH: usize = 16;
+
+thread_local! {
+    /// Per-thread stack of in-flight [`Region`] peak-resident watermarks,
+    /// innermost at `REGION_DEPTH - 1`. Every allocation bumps each active
+    /// entry, so nested regions each see their own peak regardless of what
+    /// else is going on.
+    static REGION_PEAKS: Cell<[u64; MAX_REGION_DEPTH]> = const { Cell::new([0; MAX_REGION_DEPTH]) };
+    static REGION_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A [`GlobalAlloc`] wrapper that counts bytes allocated/freed, total
+/// allocations, and peak resident bytes, forwarding the actual work to
+/// `A` (the `System` allocator by default).
+///
+/// ```
+/// use counting_allocator::CountingAllocator;
+/// use std::alloc::System;
+///
+/// #[global_allocator]
+/// static ALLOC: CountingAllocator<System> = CountingAllocator::new(System);
+///
+/// let before = ALLOC.allocations();
+/// let v = vec![1u8, 2, 3];
+/// assert!(ALLOC.allocations() > before);
+/// drop(v);
+/// ```
+pub struct CountingAllocator<A = System> {
+    inner: A,
+    bytes_allocated: AtomicU64,
+    bytes_freed: AtomicU64,
+    allocations: AtomicU64,
+    peak_resident: AtomicU64,
+}
+
+impl<A> CountingAllocator<A> {
+    /// Wraps `inner`, starting all counters at zero.
+    pub const fn new(inner: A) -> Self {
+        Self {
+            inner,
+            bytes_allocated: AtomicU64::new(0),
+            bytes_freed: AtomicU64::new(0),
+            allocations: AtomicU64::new(0),
+            peak_resident: AtomicU64::new(0),
+        }
+    }
+
+    /// Total bytes ever handed out by `alloc`/`alloc_zeroed`, plus growth
+    /// from `realloc`.
+    pub fn bytes_allocated(&self) -> u64 {
+        self.bytes_allocated.load(Ordering::Relaxed)
+    }
+
+    /// Total bytes ever returned via `dealloc`, plus shrinkage from
+    /// `realloc`.
+    pub fn bytes_freed(&self) -> u64 {
+        self.bytes_freed.load(Ordering::Relaxed)
+    }
+
+    /// Number of `alloc`/`alloc_zeroed` calls that returned a non-null
+    /// pointer.
+    pub fn allocations(&self) -> u64 {
+        self.allocations.load(Ordering::Relaxed)
+    }
+
+    /// Bytes currently live (`bytes_allocated - bytes_freed`).
+    pub fn resident(&self) -> u64 {
+        self.bytes_allocated().saturating_sub(self.bytes_freed())
+    }
+
+    /// Highest `resident()` ever observed, process-wide, since this
+    /// allocator was created.
+    pub fn peak_resident(&self) -> u64 {
+        self.peak_resident.load(Ordering::Relaxed)
+    }
+
+    fn record_alloc(&self, size: usize) {
+        self.bytes_allocated.fetch_add(size as u64, Ordering::Relaxed);
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        self.update_peak();
+    }
+
+    fn record_dealloc(&self, size: usize) {
+        self.bytes_freed.fetch_add(size as u64, Ordering::Relaxed);
+    }
+
+    fn update_peak(&self) {
+        let resident = self.resident();
+        self.peak_resident.fetch_max(resident, Ordering::Relaxed);
+        let depth = REGION_DEPTH.with(Cell::get);
+        if depth > 0 {
+            REGION_PEAKS.with(|cell| {
+                let mut peaks = cell.get();
+                for peak in &mut peaks[..depth] {
+                    if resident > *peak {
+                        *peak = resident;
+                    }
+                }
+                cell.set(peaks);
+            });
+        }
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        self.record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.inner.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                // Growth adds bytes but is not a new allocation.
+                self.bytes_allocated.fetch_add((new_size - layout.size()) as u64, Ordering::Relaxed);
+                self.update_peak();
+            } else if new_size < layout.size() {
+                self.record_dealloc(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// Counter deltas captured by [`Region::measure`] for a single closure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegionStats {
+    /// Bytes allocated while the region was active.
+    pub bytes_allocated: u64,
+    /// Bytes freed while the region was active.
+    pub bytes_freed: u64,
+    /// Number of allocations made while the region was active.
+    pub allocations: u64,
+    /// Highest resident-byte watermark observed while the region was
+    /// active (absolute, not a delta from the region's starting point).
+    pub peak_resident: u64,
+}
+
+/// A guard that snapshots a [`CountingAllocator`]'s counters on creation and
+/// diffs them against the current values on [`finish`](Region::finish).
+pub struct Region<'a, A> {
+    allocator: &'a CountingAllocator<A>,
+    start_allocated: u64,
+    start_freed: u64,
+    start_allocations: u64,
+}
+
+impl<'a, A> Region<'a, A> {
+    /// Starts tracking `allocator`'s counters from their current values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than [`MAX_REGION_DEPTH`] regions are nested on the
+    /// same thread at once.
+    pub fn begin(allocator: &'a CountingAllocator<A>) -> Self {
+        let depth = REGION_DEPTH.with(Cell::get);
+        assert!(depth < MAX_REGION_DEPTH, "Region nesting exceeds MAX_REGION_DEPTH");
+        REGION_PEAKS.with(|cell| {
+            let mut peaks = cell.get();
+            peaks[depth] = allocator.resident();
+            cell.set(peaks);
+        });
+        REGION_DEPTH.with(|cell| cell.set(depth + 1));
+        Self {
+            allocator,
+            start_allocated: allocator.bytes_allocated(),
+            start_freed: allocator.bytes_freed(),
+            start_allocations: allocator.allocations(),
+        }
+    }
+
+    /// Ends tracking and returns the deltas accumulated since [`begin`](Region::begin).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`begin`](Region::begin) is still open on the calling
+    /// thread, e.g. when a `Region` begun on one thread is finished on another.
+    pub fn finish(self) -> RegionStats {
+        let depth = REGION_DEPTH.with(Cell::get);
+        assert!(depth > 0, "Region::finish called without a matching Region::begin");
+        let new_depth = depth - 1;
+        let peak_resident = REGION_PEAKS.with(|cell| cell.get()[new_depth]);
+        REGION_DEPTH.with(|cell| cell.set(new_depth));
+        RegionStats {
+            bytes_allocated: self.allocator.bytes_allocated() - self.start_allocated,
+            bytes_freed: self.allocator.bytes_freed() - self.start_freed,
+            allocations: self.allocator.allocations() - self.start_allocations,
+            peak_resident,
+        }
+    }
+
+    /// Runs `f`, returning its value alongside the [`RegionStats`] for the
+    /// allocations it made.
+    pub fn measure<F, T>(allocator: &'a CountingAllocator<A>, f: F) -> (T, RegionStats)
+    where
+        F: FnOnce() -> T,
+    {
+        let region = Self::begin(allocator);
+        let value = f();
+        (value, region.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOC: CountingAllocator<System> = CountingAllocator::new(System);
+
+    #[test]
+    fn counts_vec_allocation() {
+        let (v, stats) = Region::measure(&ALLOC, || {
+            let mut v = Vec::<u64>::with_capacity(64);
+            v.extend(0..64u64);
+            v
+        });
+        assert_eq!(v.len(), 64);
+        assert!(stats.allocations >= 1);
+        assert!(stats.bytes_allocated >= 64 * std::mem::size_of::<u64>() as u64);
+    }
+
+    #[test]
+    fn region_nets_out_freed_memory() {
+        let (_, stats) = Region::measure(&ALLOC, || {
+            let s = String::from("hello world, this string is long enough to heap allocate");
+            drop(s);
+        });
+        assert!(stats.bytes_allocated > 0);
+        assert_eq!(stats.bytes_allocated, stats.bytes_freed);
+    }
+
+    #[test]
+    fn peak_resident_tracks_in_flight_box() {
+        let (_, stats) = Region::measure(&ALLOC, || {
+            let boxed = Box::new([0u8; 4096]);
+            assert_eq!(boxed.len(), 4096);
+        });
+        assert!(stats.peak_resident >= 4096);
+    }
+
+    #[test]
+    fn nested_regions_track_independent_peaks() {
+        let (_, outer_stats) = Region::measure(&ALLOC, || {
+            let mut small = Vec::<u8>::with_capacity(16);
+            small.extend(std::iter::repeat_n(0u8, 16));
+            let (_, inner_stats) = Region::measure(&ALLOC, || {
+                let mut big = Vec::<u8>::with_capacity(8192);
+                big.extend(std::iter::repeat_n(0u8, 8192));
+                big
+            });
+            assert!(inner_stats.peak_resident >= 8192);
+        });
+        assert!(outer_stats.allocations >= 2);
+    }
+}
@@ -0,0 +1,263 @@
+//! Runnable counterpart to the "§4 Standard Library Usage" notes'
+//! discussion of how `Vec`/`String`/`Box` route through the allocator.
+//!
+//! [`eval`] parses and evaluates `+ - * /` and parenthesized arithmetic
+//! expressions in a single O(n) pass with a shunting-yard style
+//! operator/operand stack, tokenizing a borrowed `&str` by byte index.
+//! Both stacks are fixed-size arrays, so the happy path performs zero heap
+//! allocations -- a concrete case study in allocation-conscious Rust to
+//! set against the allocator-heavy examples elsewhere in this directory.
+
+use std::fmt;
+
+/// Max depth of the operator and operand stacks; bounds how deeply nested
+/// an expression can be without allocating.
+const MAX_DEPTH: usize = 32;
+
+/// Why an expression could not be evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalError {
+    /// Byte offset and value of a character that doesn't belong anywhere
+    /// in an arithmetic expression, or of the first byte of a malformed
+    /// number such as `1.2.3`.
+    UnexpectedChar(usize, u8),
+    /// Parentheses don't balance.
+    UnbalancedParens,
+    /// An operator has no left-hand operand to apply to (e.g. `+ 1`).
+    MissingOperand,
+    /// `input` was empty or all whitespace.
+    EmptyExpression,
+    /// Expression ended with a dangling operator, e.g. `1 +`.
+    TrailingOperator,
+    /// Expression nests more than [`MAX_DEPTH`] operators/parentheses deep.
+    TooDeep,
+    /// Division by zero.
+    DivisionByZero,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            EvalError::UnexpectedChar(at, byte) => {
+                write!(f, "unexpected character {:?} at byte {at}", byte as char)
+            }
+            EvalError::UnbalancedParens => write!(f, "unbalanced parentheses"),
+            EvalError::MissingOperand => write!(f, "operator is missing an operand"),
+            EvalError::EmptyExpression => write!(f, "empty expression"),
+            EvalError::TrailingOperator => write!(f, "expression ends with an operator"),
+            EvalError::TooDeep => write!(f, "expression is nested more than {MAX_DEPTH} deep"),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// A fixed-capacity LIFO stack backed by an array, so pushing never
+/// allocates.
+struct Stack<T, const N: usize> {
+    items: [T; N],
+    len: usize,
+}
+
+impl<T: Copy + Default, const N: usize> Stack<T, N> {
+    fn new() -> Self {
+        Self { items: [T::default(); N], len: 0 }
+    }
+
+    fn push(&mut self, value: T) -> Result<(), EvalError> {
+        if self.len == N {
+            return Err(EvalError::TooDeep);
+        }
+        self.items[self.len] = value;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(self.items[self.len])
+    }
+
+    fn last(&self) -> Option<&T> {
+        self.len.checked_sub(1).map(|i| &self.items[i])
+    }
+}
+
+fn precedence(op: u8) -> u8 {
+    match op {
+        b'+' | b'-' => 1,
+        b'*' | b'/' => 2,
+        _ => 0,
+    }
+}
+
+fn apply(op: u8, lhs: f64, rhs: f64) -> Result<f64, EvalError> {
+    match op {
+        b'+' => Ok(lhs + rhs),
+        b'-' => Ok(lhs - rhs),
+        b'*' => Ok(lhs * rhs),
+        b'/' => {
+            if rhs == 0.0 {
+                Err(EvalError::DivisionByZero)
+            } else {
+                Ok(lhs / rhs)
+            }
+        }
+        _ => unreachable!("apply called with a non-operator byte"),
+    }
+}
+
+/// Pops `ops`'s top operator and applies it to `values`'s top two
+/// operands, pushing the result back onto `values`.
+fn reduce_one(values: &mut Stack<f64, MAX_DEPTH>, ops: &mut Stack<u8, MAX_DEPTH>) -> Result<(), EvalError> {
+    let op = ops.pop().expect("reduce_one called with an empty operator stack");
+    let rhs = values.pop().ok_or(EvalError::MissingOperand)?;
+    let lhs = values.pop().ok_or(EvalError::MissingOperand)?;
+    values.push(apply(op, lhs, rhs)?)
+}
+
+/// Evaluates an arithmetic expression of `+ - * /` and parentheses over
+/// `f64` operands, tokenizing `input` by byte index with no heap
+/// allocations on the happy path.
+///
+/// ```
+/// assert_eq!(eval::eval("2 + 3 * (4 - 1)"), Ok(11.0));
+/// ```
+pub fn eval(input: &str) -> Result<f64, EvalError> {
+    let bytes = input.as_bytes();
+    let mut values: Stack<f64, MAX_DEPTH> = Stack::new();
+    let mut ops: Stack<u8, MAX_DEPTH> = Stack::new();
+    let mut saw_operand = false;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        match byte {
+            b' ' | b'\t' | b'\n' | b'\r' => i += 1,
+            b'0'..=b'9' | b'.' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                let number: f64 = input[start..i]
+                    .parse()
+                    .map_err(|_| EvalError::UnexpectedChar(start, bytes[start]))?;
+                values.push(number)?;
+                saw_operand = true;
+            }
+            b'+' | b'-' | b'*' | b'/' => {
+                if !saw_operand {
+                    return Err(EvalError::MissingOperand);
+                }
+                while let Some(&top) = ops.last() {
+                    if top != b'(' && precedence(top) >= precedence(byte) {
+                        reduce_one(&mut values, &mut ops)?;
+                    } else {
+                        break;
+                    }
+                }
+                ops.push(byte)?;
+                saw_operand = false;
+                i += 1;
+            }
+            b'(' => {
+                ops.push(b'(')?;
+                saw_operand = false;
+                i += 1;
+            }
+            b')' => {
+                loop {
+                    match ops.last() {
+                        Some(&b'(') => break,
+                        Some(_) => reduce_one(&mut values, &mut ops)?,
+                        None => return Err(EvalError::UnbalancedParens),
+                    }
+                }
+                ops.pop();
+                saw_operand = true;
+                i += 1;
+            }
+            other => return Err(EvalError::UnexpectedChar(i, other)),
+        }
+    }
+
+    if !saw_operand {
+        return Err(if values.len == 0 && ops.len == 0 {
+            EvalError::EmptyExpression
+        } else {
+            EvalError::TrailingOperator
+        });
+    }
+
+    while ops.last().is_some() {
+        if *ops.last().unwrap() == b'(' {
+            return Err(EvalError::UnbalancedParens);
+        }
+        reduce_one(&mut values, &mut ops)?;
+    }
+
+    match values.len {
+        1 => Ok(values.items[0]),
+        0 => Err(EvalError::EmptyExpression),
+        _ => Err(EvalError::MissingOperand),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use counting_allocator::{CountingAllocator, Region};
+    use std::alloc::System;
+
+    #[global_allocator]
+    static ALLOC: CountingAllocator<System> = CountingAllocator::new(System);
+
+    #[test]
+    fn happy_path_performs_zero_heap_allocations() {
+        let (result, stats) = Region::measure(&ALLOC, || eval("2 + 3 * (4 - 1)"));
+        assert_eq!(result, Ok(11.0));
+        assert_eq!(stats.allocations, 0);
+    }
+
+    #[test]
+    fn evaluates_operator_precedence_and_parens() {
+        assert_eq!(eval("2 + 3 * (4 - 1)"), Ok(11.0));
+        assert_eq!(eval("2 + 3 * 4"), Ok(14.0));
+        assert_eq!(eval("(2 + 3) * 4"), Ok(20.0));
+        assert_eq!(eval("10 / 2 / 5"), Ok(1.0));
+    }
+
+    #[test]
+    fn evaluates_decimals_and_whitespace() {
+        assert_eq!(eval("1.5 + 2.25"), Ok(3.75));
+        assert_eq!(eval("  1  +  1  "), Ok(2.0));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(eval(""), Err(EvalError::EmptyExpression));
+        assert_eq!(eval("1 +"), Err(EvalError::TrailingOperator));
+        assert_eq!(eval("+ 1"), Err(EvalError::MissingOperand));
+        assert_eq!(eval("(1 + 2"), Err(EvalError::UnbalancedParens));
+        assert_eq!(eval("1 + 2)"), Err(EvalError::UnbalancedParens));
+        assert_eq!(eval("1 / 0"), Err(EvalError::DivisionByZero));
+        assert!(matches!(eval("1 & 2"), Err(EvalError::UnexpectedChar(2, b'&'))));
+    }
+
+    #[test]
+    fn rejects_expressions_nested_past_max_depth() {
+        let mut expr = String::new();
+        for _ in 0..MAX_DEPTH + 1 {
+            expr.push('(');
+        }
+        expr.push('1');
+        for _ in 0..MAX_DEPTH + 1 {
+            expr.push(')');
+        }
+        assert_eq!(eval(&expr), Err(EvalError::TooDeep));
+    }
+}
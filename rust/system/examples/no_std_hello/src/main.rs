@@ -0,0 +1,51 @@
+//! Runnable counterpart to the "§7 no_std Mode" / "§2 Entry Point" notes.
+//!
+//! This binary has no libc, no CRT, and no Rust runtime shim: it defines its
+//! own `_start`, writes "Hello" with a raw `write(2)` syscall, and exits with
+//! `exit(2)`. Build and inspect it with `check.sh` in this directory.
+
+#![no_std]
+#![no_main]
+
+use core::arch::asm;
+use core::panic::PanicInfo;
+
+const SYS_WRITE: usize = 1;
+const SYS_EXIT: usize = 60;
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    unsafe { exit(101) }
+}
+
+unsafe fn write(fd: usize, buf: *const u8, len: usize) -> isize {
+    let ret: isize;
+    asm!(
+        "syscall",
+        inlateout("rax") SYS_WRITE => ret,
+        in("rdi") fd,
+        in("rsi") buf,
+        in("rdx") len,
+        out("rcx") _,
+        out("r11") _,
+    );
+    ret
+}
+
+unsafe fn exit(code: i32) -> ! {
+    asm!(
+        "syscall",
+        in("rax") SYS_EXIT,
+        in("rdi") code,
+        options(noreturn),
+    );
+}
+
+#[no_mangle]
+pub extern "C" fn _start() -> ! {
+    let msg = b"Hello\n";
+    unsafe {
+        write(1, msg.as_ptr(), msg.len());
+        exit(0);
+    }
+}
@@ -0,0 +1,139 @@
+//! Runnable counterpart to the "§5 Error Handling" notes' claim that
+//! unwinding "requires extra metadata" and removing it "lets the compiler
+//! elide work around calls".
+//!
+//! Compiles the same `swap(a, b); g(); swap(a, b)` function once with
+//! `panic=unwind` and once with `panic=abort` (`g` is an opaque, possibly-
+//! panicking `extern "C"` function the compiler can't see through), emits
+//! `--emit asm` for both at the default (unoptimized) opt-level, and
+//! compares them: with unwinding possible, the compiler has to emit a
+//! landing pad, a `.gcc_except_table`, and `panic_cannot_unwind` calls for
+//! every intrinsic along the way; with `panic=abort` none of that unwind
+//! bookkeeping can ever run, so it's dropped outright. (At `-O`, LLVM
+//! proves the swap pair is a no-op either way and the difference vanishes
+//! -- this is squarely an "unoptimized code has real unwind overhead"
+//! demo, not an "`-O` gets better with abort" one.)
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const SOURCE: &str = r#"
+unsafe extern "C" {
+    fn g();
+}
+
+#[no_mangle]
+pub fn swap_around_call(a: &mut i32, b: &mut i32) {
+    std::mem::swap(a, b);
+    unsafe { g() };
+    std::mem::swap(a, b);
+}
+"#;
+
+fn emit_asm(work_dir: &Path, panic_strategy: &str) -> Result<String, Box<dyn Error>> {
+    let src_path = work_dir.join(format!("swap_{panic_strategy}.rs"));
+    let asm_path = work_dir.join(format!("swap_{panic_strategy}.s"));
+    fs::write(&src_path, SOURCE)?;
+
+    let status = Command::new("rustc")
+        .arg(&src_path)
+        .arg("--crate-name")
+        .arg("swap_demo")
+        .arg("--crate-type")
+        .arg("lib")
+        .arg("--emit")
+        .arg("asm")
+        .arg("-o")
+        .arg(&asm_path)
+        .args(["-C", &format!("panic={panic_strategy}")])
+        .status()?;
+    if !status.success() {
+        return Err(format!("rustc failed for panic={panic_strategy}").into());
+    }
+
+    Ok(fs::read_to_string(&asm_path)?)
+}
+
+/// Counts assembly instruction lines: skips blank lines, comments,
+/// assembler directives (`.cfi_*`, `.section`, ...), and labels.
+fn count_instructions(asm: &str) -> usize {
+    asm.lines()
+        .map(str::trim)
+        .filter(|line| {
+            !line.is_empty()
+                && !line.starts_with('.')
+                && !line.starts_with('#')
+                && !line.ends_with(':')
+        })
+        .count()
+}
+
+/// Whether the assembly has unwinding landing-pad metadata: either a
+/// `.cfi_personality` directive or a `.gcc_except_table` section. Plain
+/// `.cfi_startproc`/`.cfi_endproc` frame directives are emitted either way
+/// (they just describe the stack frame for debuggers), so those alone
+/// don't indicate unwind support.
+fn has_unwind_metadata(asm: &str) -> bool {
+    asm.contains(".cfi_personality") || asm.contains(".gcc_except_table")
+}
+
+fn compare(work_dir: &Path) -> Result<(String, String, usize, usize), Box<dyn Error>> {
+    let unwind_asm = emit_asm(work_dir, "unwind")?;
+    let abort_asm = emit_asm(work_dir, "abort")?;
+    let unwind_count = count_instructions(&unwind_asm);
+    let abort_count = count_instructions(&abort_asm);
+    Ok((unwind_asm, abort_asm, unwind_count, abort_count))
+}
+
+fn work_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("panic_unwind_vs_abort_{}", std::process::id()))
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let dir = work_dir();
+    fs::create_dir_all(&dir)?;
+    let (unwind_asm, abort_asm, unwind_count, abort_count) = compare(&dir)?;
+    fs::remove_dir_all(&dir).ok();
+
+    println!("panic=unwind: {unwind_count} instructions, unwind metadata: {}", has_unwind_metadata(&unwind_asm));
+    println!("panic=abort:  {abort_count} instructions, unwind metadata: {}", has_unwind_metadata(&abort_asm));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_instructions_skips_directives_labels_and_blanks() {
+        let asm = "\t.text\nswap_around_call:\n\tpushq %rbp\n\t# a comment\n\tretq\n";
+        assert_eq!(count_instructions(asm), 2);
+    }
+
+    #[test]
+    fn abort_build_has_no_unwind_metadata() {
+        let dir = work_dir().join("unwind_metadata_test");
+        fs::create_dir_all(&dir).unwrap();
+        let abort_asm = emit_asm(&dir, "abort").unwrap();
+        fs::remove_dir_all(&dir).ok();
+        assert!(!has_unwind_metadata(&abort_asm));
+    }
+
+    #[test]
+    fn abort_build_has_strictly_fewer_instructions_and_loses_unwind_metadata() {
+        let dir = work_dir().join("instruction_count_test");
+        fs::create_dir_all(&dir).unwrap();
+        let (unwind_asm, abort_asm, unwind_count, abort_count) = compare(&dir).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(has_unwind_metadata(&unwind_asm));
+        assert!(!has_unwind_metadata(&abort_asm));
+        assert!(
+            abort_count < unwind_count,
+            "expected panic=abort ({abort_count} instructions) to need fewer \
+             instructions than panic=unwind ({unwind_count})"
+        );
+    }
+}